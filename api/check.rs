@@ -1,15 +1,44 @@
-use chrono::Utc;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use hickory_resolver::config::{NameServerConfigGroup, ResolverConfig, ResolverOpts};
+use hickory_resolver::TokioAsyncResolver;
+use once_cell::sync::Lazy;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
 use std::time::Duration;
+use url::Url;
 use vercel_runtime::{run, Body, Error, Request, Response, StatusCode};
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 struct Domain {
     name: String,
     url: String,
     #[serde(default = "default_timeout")]
     timeout_seconds: u64,
+    /// HTTP status the response must match exactly; any 2xx is accepted
+    /// when unset.
+    #[serde(default)]
+    expected_status: Option<u16>,
+    /// Substring that must appear in the response body.
+    #[serde(default)]
+    body_contains: Option<String>,
+    /// Substring that must NOT appear in the response body (e.g. a
+    /// maintenance-page marker).
+    #[serde(default)]
+    body_not_contains: Option<String>,
+    /// Fail the check if the response takes longer than this, even if the
+    /// status and body assertions pass.
+    #[serde(default)]
+    max_response_time_ms: Option<u64>,
+    /// Static hostname -> IP overrides for this domain, like curl's
+    /// `--resolve`. Lets us pin a check to a specific origin/CDN edge.
+    #[serde(default)]
+    resolve: Option<HashMap<String, String>>,
 }
 
 fn default_timeout() -> u64 {
@@ -21,7 +50,7 @@ struct DomainsConfig {
     domains: Vec<Domain>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 struct CheckResult {
     name: String,
     url: String,
@@ -29,6 +58,8 @@ struct CheckResult {
     error: Option<String>,
     status_code: Option<u16>,
     response_time_ms: Option<u64>,
+    dns_time_ms: Option<u64>,
+    consecutive_failures: Option<u32>,
 }
 
 #[derive(Debug, Serialize)]
@@ -52,31 +83,533 @@ struct SlackText {
     text: String,
 }
 
-async fn check_domain(client: &Client, domain: &Domain) -> CheckResult {
+/// Up/down state of a domain as tracked by its `Breaker`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+enum DomainState {
+    Up,
+    Down,
+}
+
+/// Per-domain failure tracker used to suppress alert flapping. One `Breaker`
+/// is kept per domain name and persisted across invocations so a cron run
+/// can tell whether a failure is a fresh transition or a continuation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Breaker {
+    consecutive_failures: u32,
+    last_attempt: DateTime<Utc>,
+    last_state: DomainState,
+}
+
+/// Re-alert on a failing domain at 1, 2, 4, 8... check cycles so a flapping
+/// domain doesn't spam the channel on every single cron tick.
+const BREAKER_BACKOFF_CAP: u32 = 64;
+
+fn backoff_elapsed(consecutive_failures: u32) -> bool {
+    if consecutive_failures == 0 {
+        return false;
+    }
+    if consecutive_failures >= BREAKER_BACKOFF_CAP {
+        return consecutive_failures % BREAKER_BACKOFF_CAP == 0;
+    }
+    consecutive_failures.is_power_of_two()
+}
+
+/// Storage backend for `Breaker` state. Implementations persist breakers
+/// across invocations (e.g. Vercel KV) or, when no store is configured,
+/// fall back to an in-process map that only survives within a single
+/// warm instance.
+#[async_trait]
+trait BreakerStore: Send + Sync {
+    async fn get(&self, domain: &str) -> Option<Breaker>;
+    async fn set(&self, domain: &str, breaker: &Breaker);
+}
+
+static IN_MEMORY_BREAKERS: Lazy<DashMap<String, Breaker>> = Lazy::new(DashMap::new);
+
+struct InMemoryBreakerStore;
+
+#[async_trait]
+impl BreakerStore for InMemoryBreakerStore {
+    async fn get(&self, domain: &str) -> Option<Breaker> {
+        IN_MEMORY_BREAKERS.get(domain).map(|entry| entry.clone())
+    }
+
+    async fn set(&self, domain: &str, breaker: &Breaker) {
+        IN_MEMORY_BREAKERS.insert(domain.to_string(), breaker.clone());
+    }
+}
+
+/// Thin client over the Vercel KV (Upstash Redis) REST API, configured via
+/// `KV_REST_API_URL` / `KV_REST_API_TOKEN`. Shared by every store that needs
+/// cross-invocation persistence (breakers, history).
+#[derive(Clone)]
+struct KvClient {
+    client: Client,
+    rest_url: String,
+    rest_token: String,
+}
+
+impl KvClient {
+    async fn get_json<T: serde::de::DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let url = format!("{}/get/{}", self.rest_url, key);
+        let response = self
+            .client
+            .get(&url)
+            .bearer_auth(&self.rest_token)
+            .send()
+            .await
+            .ok()?;
+        let body: serde_json::Value = response.json().await.ok()?;
+        let raw = body.get("result")?.as_str()?;
+        serde_json::from_str(raw).ok()
+    }
+
+    async fn set_json<T: Serialize>(&self, key: &str, value: &T) {
+        let url = format!("{}/set/{}", self.rest_url, key);
+        let payload = match serde_json::to_string(value) {
+            Ok(payload) => payload,
+            Err(e) => {
+                eprintln!("Failed to serialize value for key {}: {}", key, e);
+                return;
+            }
+        };
+        if let Err(e) = self
+            .client
+            .post(&url)
+            .bearer_auth(&self.rest_token)
+            .body(payload)
+            .send()
+            .await
+        {
+            eprintln!("Failed to persist key {}: {}", key, e);
+        }
+    }
+}
+
+fn build_kv_client(client: &Client) -> Option<KvClient> {
+    match (
+        std::env::var("KV_REST_API_URL"),
+        std::env::var("KV_REST_API_TOKEN"),
+    ) {
+        (Ok(rest_url), Ok(rest_token)) => Some(KvClient {
+            client: client.clone(),
+            rest_url,
+            rest_token,
+        }),
+        _ => None,
+    }
+}
+
+struct KvBreakerStore {
+    kv: KvClient,
+}
+
+impl KvBreakerStore {
+    fn key(domain: &str) -> String {
+        format!("breaker:{}", domain)
+    }
+}
+
+#[async_trait]
+impl BreakerStore for KvBreakerStore {
+    async fn get(&self, domain: &str) -> Option<Breaker> {
+        self.kv.get_json(&Self::key(domain)).await
+    }
+
+    async fn set(&self, domain: &str, breaker: &Breaker) {
+        self.kv.set_json(&Self::key(domain), breaker).await;
+    }
+}
+
+fn build_breaker_store(kv: &Option<KvClient>) -> Arc<dyn BreakerStore> {
+    match kv {
+        Some(kv) => Arc::new(KvBreakerStore { kv: kv.clone() }),
+        None => Arc::new(InMemoryBreakerStore),
+    }
+}
+
+/// A single historical check, persisted so the status dashboard can show a
+/// rolling uptime percentage rather than just the most recent result.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct HistoryEntry {
+    checked_at: DateTime<Utc>,
+    success: bool,
+    status_code: Option<u16>,
+    response_time_ms: Option<u64>,
+    error: Option<String>,
+}
+
+/// How many checks to retain per domain; older entries are dropped as new
+/// ones are appended.
+const HISTORY_RING_SIZE: usize = 100;
+
+/// Storage backend for per-domain check history, mirroring `BreakerStore`.
+#[async_trait]
+trait HistoryStore: Send + Sync {
+    async fn append(&self, domain: &str, entry: HistoryEntry);
+    async fn get(&self, domain: &str) -> Vec<HistoryEntry>;
+}
+
+static IN_MEMORY_HISTORY: Lazy<DashMap<String, Vec<HistoryEntry>>> = Lazy::new(DashMap::new);
+
+struct InMemoryHistoryStore;
+
+#[async_trait]
+impl HistoryStore for InMemoryHistoryStore {
+    async fn append(&self, domain: &str, entry: HistoryEntry) {
+        let mut bucket = IN_MEMORY_HISTORY
+            .entry(domain.to_string())
+            .or_insert_with(Vec::new);
+        bucket.push(entry);
+        if bucket.len() > HISTORY_RING_SIZE {
+            let overflow = bucket.len() - HISTORY_RING_SIZE;
+            bucket.drain(0..overflow);
+        }
+    }
+
+    async fn get(&self, domain: &str) -> Vec<HistoryEntry> {
+        IN_MEMORY_HISTORY
+            .get(domain)
+            .map(|entry| entry.clone())
+            .unwrap_or_default()
+    }
+}
+
+struct KvHistoryStore {
+    kv: KvClient,
+}
+
+impl KvHistoryStore {
+    fn key(domain: &str) -> String {
+        format!("history:{}", domain)
+    }
+}
+
+#[async_trait]
+impl HistoryStore for KvHistoryStore {
+    async fn append(&self, domain: &str, entry: HistoryEntry) {
+        let key = Self::key(domain);
+        let mut history: Vec<HistoryEntry> = self.kv.get_json(&key).await.unwrap_or_default();
+        history.push(entry);
+        if history.len() > HISTORY_RING_SIZE {
+            let overflow = history.len() - HISTORY_RING_SIZE;
+            history.drain(0..overflow);
+        }
+        self.kv.set_json(&key, &history).await;
+    }
+
+    async fn get(&self, domain: &str) -> Vec<HistoryEntry> {
+        self.kv
+            .get_json(&Self::key(domain))
+            .await
+            .unwrap_or_default()
+    }
+}
+
+fn build_history_store(kv: &Option<KvClient>) -> Arc<dyn HistoryStore> {
+    match kv {
+        Some(kv) => Arc::new(KvHistoryStore { kv: kv.clone() }),
+        None => Arc::new(InMemoryHistoryStore),
+    }
+}
+
+/// Outcome of running a `CheckResult` through its domain's breaker: whether
+/// this run should actually notify, and the running failure count to
+/// surface to operators.
+struct BreakerEvaluation {
+    should_alert_down: bool,
+    should_alert_recovered: bool,
+    consecutive_failures: u32,
+}
+
+/// Sits between `check_domain` and the notification step, deciding whether a
+/// result is a state *transition* worth alerting on or a continuation that
+/// should be suppressed (subject to exponential backoff).
+struct Breakers {
+    store: Arc<dyn BreakerStore>,
+}
+
+impl Breakers {
+    fn new(store: Arc<dyn BreakerStore>) -> Self {
+        Breakers { store }
+    }
+
+    async fn evaluate(&self, result: &CheckResult) -> BreakerEvaluation {
+        let previous = self.store.get(&result.name).await;
+        let now = Utc::now();
+
+        let (should_alert_down, should_alert_recovered, next) = match (&previous, result.success) {
+            (None, true) => (
+                false,
+                false,
+                Breaker {
+                    consecutive_failures: 0,
+                    last_attempt: now,
+                    last_state: DomainState::Up,
+                },
+            ),
+            (None, false) => (
+                true,
+                false,
+                Breaker {
+                    consecutive_failures: 1,
+                    last_attempt: now,
+                    last_state: DomainState::Down,
+                },
+            ),
+            (Some(prev), true) => {
+                let recovered = prev.last_state == DomainState::Down;
+                (
+                    false,
+                    recovered,
+                    Breaker {
+                        consecutive_failures: 0,
+                        last_attempt: now,
+                        last_state: DomainState::Up,
+                    },
+                )
+            }
+            (Some(prev), false) => {
+                let consecutive_failures = prev.consecutive_failures + 1;
+                let transitioned = prev.last_state == DomainState::Up;
+                let alert = transitioned || backoff_elapsed(consecutive_failures);
+                (
+                    alert,
+                    false,
+                    Breaker {
+                        consecutive_failures,
+                        last_attempt: now,
+                        last_state: DomainState::Down,
+                    },
+                )
+            }
+        };
+
+        self.store.set(&result.name, &next).await;
+
+        BreakerEvaluation {
+            should_alert_down,
+            should_alert_recovered,
+            consecutive_failures: next.consecutive_failures,
+        }
+    }
+}
+
+/// Resolves hostnames against a configurable upstream DNS server instead of
+/// the system resolver, so we can monitor an origin that isn't in public DNS
+/// yet. Configured via `DNS_SERVER`: an IPv4 address (port 53 is used) or an
+/// IP:port socket address such as `[2001:db8::1]:53`. A value that doesn't
+/// parse that way, e.g. a hostname, leaves the system resolver in use.
+struct CustomDnsResolver {
+    resolver: Arc<TokioAsyncResolver>,
+}
+
+impl Resolve for CustomDnsResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let resolver = self.resolver.clone();
+        Box::pin(async move {
+            let lookup = resolver
+                .lookup_ip(name.as_str())
+                .await
+                .map_err(|e| -> Box<dyn std::error::Error + Send + Sync> { Box::new(e) })?;
+            let addrs: Addrs = Box::new(lookup.into_iter().map(|ip| SocketAddr::new(ip, 0)));
+            Ok(addrs)
+        })
+    }
+}
+
+fn build_custom_dns_resolver() -> Option<Arc<TokioAsyncResolver>> {
+    let dns_server = std::env::var("DNS_SERVER").ok()?;
+    let socket_addr: SocketAddr = if dns_server.contains(':') {
+        dns_server.parse().ok()?
+    } else {
+        format!("{}:53", dns_server).parse().ok()?
+    };
+    let group =
+        NameServerConfigGroup::from_ips_clear(&[socket_addr.ip()], socket_addr.port(), true);
+    let config = ResolverConfig::from_parts(None, vec![], group);
+    Some(Arc::new(TokioAsyncResolver::tokio(
+        config,
+        ResolverOpts::default(),
+    )))
+}
+
+fn extract_host(url_str: &str) -> Option<String> {
+    Url::parse(url_str).ok()?.host_str().map(|h| h.to_string())
+}
+
+/// Builds a `Client` carrying the given per-domain `resolve` overrides (if
+/// any) and the shared custom DNS resolver (if configured). Each domain's
+/// check gets its own `Client`, since `.resolve()` is keyed by hostname on
+/// whichever `Client` it's registered on and can't be scoped to a single
+/// domain otherwise.
+fn build_domain_client(
+    overrides: Option<&HashMap<String, String>>,
+    custom_resolver: &Option<Arc<TokioAsyncResolver>>,
+) -> Result<Client, String> {
+    let mut builder = Client::builder().user_agent("MagnetWatchtower/1.0");
+
+    if let Some(overrides) = overrides {
+        for (host, ip) in overrides {
+            let addr =
+                parse_resolve_override(ip).map_err(|e| format!("{} ({} -> {})", e, host, ip))?;
+            builder = builder.resolve(host, addr);
+        }
+    }
+
+    if let Some(resolver) = custom_resolver {
+        builder = builder.dns_resolver(Arc::new(CustomDnsResolver {
+            resolver: resolver.clone(),
+        }));
+    }
+
+    builder.build().map_err(|e| e.to_string())
+}
+
+/// Parses a `resolve` override IP into a `SocketAddr`, used both to
+/// validate the override and to register it on a `Client`. Goes through
+/// `IpAddr` rather than formatting `"{ip}:0"` and parsing that directly, so
+/// IPv6 overrides (which need bracket syntax, e.g. `"[::1]:0"`) work the
+/// same as IPv4 ones.
+fn parse_resolve_override(ip: &str) -> Result<SocketAddr, String> {
+    ip.parse::<std::net::IpAddr>()
+        .map(|addr| SocketAddr::new(addr, 0))
+        .map_err(|_| format!("Invalid resolve override IP: {}", ip))
+}
+
+/// Shared DNS state used by every domain's check task so resolution time
+/// can be measured up front, before `check_domain` makes the HTTP request.
+struct DnsContext {
+    resolver: Option<Arc<TokioAsyncResolver>>,
+}
+
+impl DnsContext {
+    /// Resolves `host`, honoring `domain`'s per-host override, and returns
+    /// how long that took plus the resolved address (or an error). The
+    /// caller pins the returned address onto the `Client` used for the
+    /// actual request (via `.resolve()`), so the connection reuses this
+    /// lookup instead of triggering a second, independent one.
+    async fn resolve_timed(
+        &self,
+        domain: &Domain,
+        host: &str,
+    ) -> (u64, Result<SocketAddr, String>) {
+        let start = std::time::Instant::now();
+
+        if let Some(ip) = domain
+            .resolve
+            .as_ref()
+            .and_then(|overrides| overrides.get(host))
+        {
+            return (
+                start.elapsed().as_millis() as u64,
+                parse_resolve_override(ip),
+            );
+        }
+
+        let result = match &self.resolver {
+            Some(resolver) => resolver
+                .lookup_ip(host)
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|lookup| {
+                    lookup
+                        .into_iter()
+                        .next()
+                        .map(|ip| SocketAddr::new(ip, 0))
+                        .ok_or_else(|| "DNS resolution returned no addresses".to_string())
+                }),
+            None => tokio::net::lookup_host((host, 0))
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|mut addrs| {
+                    addrs
+                        .next()
+                        .ok_or_else(|| "DNS resolution returned no addresses".to_string())
+                }),
+        };
+
+        let elapsed = start.elapsed().as_millis() as u64;
+        (
+            elapsed,
+            result.map_err(|e| format!("DNS resolution failed: {}", e)),
+        )
+    }
+}
+
+/// Runs the HTTP check itself. DNS resolution happens in the caller, which
+/// pins the resolved address onto `client` before handing it here, so this
+/// function never triggers its own, separate lookup.
+async fn check_domain(client: &Client, domain: &Domain, dns_time_ms: Option<u64>) -> CheckResult {
     let start = std::time::Instant::now();
     let timeout = Duration::from_secs(domain.timeout_seconds);
 
-    match client
-        .get(&domain.url)
-        .timeout(timeout)
-        .send()
-        .await
-    {
+    match client.get(&domain.url).timeout(timeout).send().await {
         Ok(response) => {
             let status = response.status();
+            // Only pay for a body download when an assertion actually
+            // needs it — large/binary responses shouldn't be pulled into
+            // memory, and response_time_ms shouldn't include a download no
+            // one asked for.
+            let needs_body = domain.body_contains.is_some() || domain.body_not_contains.is_some();
+            let body = if needs_body {
+                response.text().await.unwrap_or_default()
+            } else {
+                String::new()
+            };
             let response_time = start.elapsed().as_millis() as u64;
-            
+
+            let mut errors = Vec::new();
+
+            let status_ok = match domain.expected_status {
+                Some(expected) => status.as_u16() == expected,
+                None => status.is_success(),
+            };
+            if !status_ok {
+                errors.push(match domain.expected_status {
+                    Some(expected) => {
+                        format!("Expected HTTP {}, got {}", expected, status.as_u16())
+                    }
+                    None => format!("HTTP {}", status.as_u16()),
+                });
+            }
+
+            if let Some(needle) = &domain.body_contains {
+                if !body.contains(needle.as_str()) {
+                    errors.push(format!("Body missing expected text: {:?}", needle));
+                }
+            }
+
+            if let Some(needle) = &domain.body_not_contains {
+                if body.contains(needle.as_str()) {
+                    errors.push(format!("Body contains forbidden text: {:?}", needle));
+                }
+            }
+
+            if let Some(max_ms) = domain.max_response_time_ms {
+                if response_time > max_ms {
+                    errors.push(format!(
+                        "Response time {}ms exceeded threshold of {}ms",
+                        response_time, max_ms
+                    ));
+                }
+            }
+
+            let success = errors.is_empty();
+
             CheckResult {
                 name: domain.name.clone(),
                 url: domain.url.clone(),
-                success: status.is_success(),
-                error: if status.is_success() {
+                success,
+                error: if success {
                     None
                 } else {
-                    Some(format!("HTTP {}", status.as_u16()))
+                    Some(errors.join("; "))
                 },
                 status_code: Some(status.as_u16()),
                 response_time_ms: Some(response_time),
+                dns_time_ms,
+                consecutive_failures: None,
             }
         }
         Err(e) => {
@@ -98,11 +631,307 @@ async fn check_domain(client: &Client, domain: &Domain) -> CheckResult {
                 error: Some(error_msg),
                 status_code: None,
                 response_time_ms: Some(response_time),
+                dns_time_ms,
+                consecutive_failures: None,
             }
         }
     }
 }
 
+/// A destination for uptime alerts. Implementations are selected at runtime
+/// via the `NOTIFIERS` env var and fanned out concurrently, so one noisy or
+/// misconfigured destination can't block the others.
+#[async_trait]
+trait Notifier: Send + Sync {
+    fn name(&self) -> &'static str;
+    async fn notify(
+        &self,
+        failures: &[CheckResult],
+        recoveries: &[CheckResult],
+    ) -> Result<(), Error>;
+}
+
+struct SlackNotifier {
+    webhook_url: String,
+}
+
+#[async_trait]
+impl Notifier for SlackNotifier {
+    fn name(&self) -> &'static str {
+        "slack"
+    }
+
+    async fn notify(
+        &self,
+        failures: &[CheckResult],
+        recoveries: &[CheckResult],
+    ) -> Result<(), Error> {
+        send_slack_notification(&self.webhook_url, failures).await?;
+        send_slack_recovery_notification(&self.webhook_url, recoveries).await?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct DiscordMessage {
+    embeds: Vec<DiscordEmbed>,
+}
+
+#[derive(Debug, Serialize)]
+struct DiscordEmbed {
+    title: String,
+    color: u32,
+    fields: Vec<DiscordField>,
+}
+
+#[derive(Debug, Serialize)]
+struct DiscordField {
+    name: String,
+    value: String,
+    inline: bool,
+}
+
+const DISCORD_COLOR_DOWN: u32 = 0xED4245;
+const DISCORD_COLOR_RECOVERED: u32 = 0x57F287;
+
+struct DiscordNotifier {
+    webhook_url: String,
+}
+
+impl DiscordNotifier {
+    fn embed_for(result: &CheckResult, color: u32, title_prefix: &str) -> DiscordEmbed {
+        let mut fields = vec![
+            DiscordField {
+                name: "URL".to_string(),
+                value: result.url.clone(),
+                inline: false,
+            },
+            DiscordField {
+                name: "Response Time".to_string(),
+                value: format!("{}ms", result.response_time_ms.unwrap_or(0)),
+                inline: true,
+            },
+        ];
+        if let Some(error) = &result.error {
+            fields.push(DiscordField {
+                name: "Error".to_string(),
+                value: error.clone(),
+                inline: true,
+            });
+        }
+
+        DiscordEmbed {
+            title: format!("{}: {}", title_prefix, result.name),
+            color,
+            fields,
+        }
+    }
+
+    async fn send(&self, embeds: Vec<DiscordEmbed>) -> Result<(), Error> {
+        if embeds.is_empty() {
+            return Ok(());
+        }
+
+        let client = Client::new();
+        client
+            .post(&self.webhook_url)
+            .json(&DiscordMessage { embeds })
+            .send()
+            .await
+            .map_err(|e| Error::from(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Notifier for DiscordNotifier {
+    fn name(&self) -> &'static str {
+        "discord"
+    }
+
+    async fn notify(
+        &self,
+        failures: &[CheckResult],
+        recoveries: &[CheckResult],
+    ) -> Result<(), Error> {
+        let down_embeds = failures
+            .iter()
+            .map(|f| Self::embed_for(f, DISCORD_COLOR_DOWN, "Down"))
+            .collect();
+        self.send(down_embeds).await?;
+
+        let recovered_embeds = recoveries
+            .iter()
+            .map(|r| Self::embed_for(r, DISCORD_COLOR_RECOVERED, "Recovered"))
+            .collect();
+        self.send(recovered_embeds).await?;
+
+        Ok(())
+    }
+}
+
+/// PagerDuty Events API v2 trigger/resolve, deduplicated per domain so a
+/// flapping domain reopens the same incident rather than paging on-call
+/// repeatedly.
+#[derive(Debug, Serialize)]
+struct PagerDutyEvent {
+    routing_key: String,
+    event_action: &'static str,
+    dedup_key: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    payload: Option<PagerDutyPayload>,
+}
+
+#[derive(Debug, Serialize)]
+struct PagerDutyPayload {
+    summary: String,
+    source: String,
+    severity: &'static str,
+}
+
+const PAGERDUTY_EVENTS_URL: &str = "https://events.pagerduty.com/v2/enqueue";
+
+struct PagerDutyNotifier {
+    routing_key: String,
+}
+
+impl PagerDutyNotifier {
+    fn dedup_key(domain_name: &str) -> String {
+        format!("magnet-watchtower:{}", domain_name)
+    }
+
+    async fn send(&self, event: PagerDutyEvent) -> Result<(), Error> {
+        let client = Client::new();
+        client
+            .post(PAGERDUTY_EVENTS_URL)
+            .json(&event)
+            .send()
+            .await
+            .map_err(|e| Error::from(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Notifier for PagerDutyNotifier {
+    fn name(&self) -> &'static str {
+        "pagerduty"
+    }
+
+    async fn notify(
+        &self,
+        failures: &[CheckResult],
+        recoveries: &[CheckResult],
+    ) -> Result<(), Error> {
+        for failure in failures {
+            let event = PagerDutyEvent {
+                routing_key: self.routing_key.clone(),
+                event_action: "trigger",
+                dedup_key: Self::dedup_key(&failure.name),
+                payload: Some(PagerDutyPayload {
+                    summary: failure
+                        .error
+                        .clone()
+                        .unwrap_or_else(|| "Domain check failed".to_string()),
+                    source: failure.url.clone(),
+                    severity: "critical",
+                }),
+            };
+            self.send(event).await?;
+        }
+
+        for recovery in recoveries {
+            let event = PagerDutyEvent {
+                routing_key: self.routing_key.clone(),
+                event_action: "resolve",
+                dedup_key: Self::dedup_key(&recovery.name),
+                payload: None,
+            };
+            self.send(event).await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// POSTs the raw failures/recoveries payload as JSON, for teams routing
+/// alerts into tooling that doesn't have a bespoke `Notifier` impl here.
+struct GenericWebhookNotifier {
+    url: String,
+}
+
+#[async_trait]
+impl Notifier for GenericWebhookNotifier {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    async fn notify(
+        &self,
+        failures: &[CheckResult],
+        recoveries: &[CheckResult],
+    ) -> Result<(), Error> {
+        let payload = serde_json::json!({
+            "timestamp": Utc::now().to_rfc3339(),
+            "failures": failures,
+            "recoveries": recoveries,
+        });
+
+        let client = Client::new();
+        client
+            .post(&self.url)
+            .json(&payload)
+            .send()
+            .await
+            .map_err(|e| Error::from(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+/// Builds the active notifier set from `NOTIFIERS` (comma-separated, e.g.
+/// `NOTIFIERS=slack,discord`), skipping any notifier whose required env vars
+/// aren't set. Defaults to `slack` alone when `NOTIFIERS` is unset, to match
+/// prior behavior.
+fn build_notifiers() -> Vec<Box<dyn Notifier>> {
+    let configured = std::env::var("NOTIFIERS").unwrap_or_else(|_| "slack".to_string());
+    let mut notifiers: Vec<Box<dyn Notifier>> = Vec::new();
+
+    for name in configured
+        .split(',')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+    {
+        match name {
+            "slack" => match std::env::var("SLACK_WEBHOOK_URL") {
+                Ok(webhook_url) => notifiers.push(Box::new(SlackNotifier { webhook_url })),
+                Err(_) => eprintln!("NOTIFIERS includes slack but SLACK_WEBHOOK_URL is not set"),
+            },
+            "discord" => match std::env::var("DISCORD_WEBHOOK_URL") {
+                Ok(webhook_url) => notifiers.push(Box::new(DiscordNotifier { webhook_url })),
+                Err(_) => {
+                    eprintln!("NOTIFIERS includes discord but DISCORD_WEBHOOK_URL is not set")
+                }
+            },
+            "pagerduty" => match std::env::var("PAGERDUTY_ROUTING_KEY") {
+                Ok(routing_key) => notifiers.push(Box::new(PagerDutyNotifier { routing_key })),
+                Err(_) => {
+                    eprintln!("NOTIFIERS includes pagerduty but PAGERDUTY_ROUTING_KEY is not set")
+                }
+            },
+            "webhook" => match std::env::var("WEBHOOK_URL") {
+                Ok(url) => notifiers.push(Box::new(GenericWebhookNotifier { url })),
+                Err(_) => eprintln!("NOTIFIERS includes webhook but WEBHOOK_URL is not set"),
+            },
+            other => eprintln!("Unknown notifier '{}' in NOTIFIERS, skipping", other),
+        }
+    }
+
+    notifiers
+}
+
 async fn send_slack_notification(webhook_url: &str, failures: &[CheckResult]) -> Result<(), Error> {
     if failures.is_empty() {
         return Ok(());
@@ -110,7 +939,7 @@ async fn send_slack_notification(webhook_url: &str, failures: &[CheckResult]) ->
 
     let timestamp = Utc::now().format("%Y-%m-%d %H:%M:%S UTC").to_string();
     let failure_count = failures.len();
-    
+
     let header_text = format!(
         "🚨 *Uptime Alert: {} domain{} down*",
         failure_count,
@@ -122,7 +951,11 @@ async fn send_slack_notification(webhook_url: &str, failures: &[CheckResult]) ->
             block_type: "header".to_string(),
             text: Some(SlackText {
                 text_type: "plain_text".to_string(),
-                text: format!("Uptime Alert: {} domain{} down", failure_count, if failure_count == 1 { " is" } else { "s are" }),
+                text: format!(
+                    "Uptime Alert: {} domain{} down",
+                    failure_count,
+                    if failure_count == 1 { " is" } else { "s are" }
+                ),
             }),
             fields: None,
         },
@@ -142,8 +975,11 @@ async fn send_slack_notification(webhook_url: &str, failures: &[CheckResult]) ->
     ];
 
     for failure in failures {
-        let error_text = failure.error.as_ref().unwrap_or(&"Unknown error".to_string());
-        
+        let error_text = failure
+            .error
+            .as_ref()
+            .unwrap_or(&"Unknown error".to_string());
+
         blocks.push(SlackBlock {
             block_type: "section".to_string(),
             text: None,
@@ -162,7 +998,17 @@ async fn send_slack_notification(webhook_url: &str, failures: &[CheckResult]) ->
                 },
                 SlackText {
                     text_type: "mrkdwn".to_string(),
-                    text: format!("*Response Time:*\n{}ms", failure.response_time_ms.unwrap_or(0)),
+                    text: format!(
+                        "*Response Time:*\n{}ms",
+                        failure.response_time_ms.unwrap_or(0)
+                    ),
+                },
+                SlackText {
+                    text_type: "mrkdwn".to_string(),
+                    text: format!(
+                        "*Consecutive Failures:*\n{}",
+                        failure.consecutive_failures.unwrap_or(0)
+                    ),
                 },
             ]),
         });
@@ -184,30 +1030,335 @@ async fn send_slack_notification(webhook_url: &str, failures: &[CheckResult]) ->
     Ok(())
 }
 
+async fn send_slack_recovery_notification(
+    webhook_url: &str,
+    recoveries: &[CheckResult],
+) -> Result<(), Error> {
+    if recoveries.is_empty() {
+        return Ok(());
+    }
+
+    let timestamp = Utc::now().format("%Y-%m-%d %H:%M:%S UTC").to_string();
+    let recovery_count = recoveries.len();
+
+    let header_text = format!(
+        "✅ *Recovered: {} domain{} back up*",
+        recovery_count,
+        if recovery_count == 1 { " is" } else { "s are" }
+    );
+
+    let mut blocks = vec![
+        SlackBlock {
+            block_type: "header".to_string(),
+            text: Some(SlackText {
+                text_type: "plain_text".to_string(),
+                text: format!(
+                    "Recovered: {} domain{} back up",
+                    recovery_count,
+                    if recovery_count == 1 { " is" } else { "s are" }
+                ),
+            }),
+            fields: None,
+        },
+        SlackBlock {
+            block_type: "section".to_string(),
+            text: Some(SlackText {
+                text_type: "mrkdwn".to_string(),
+                text: format!("*Check Time:* {}", timestamp),
+            }),
+            fields: None,
+        },
+        SlackBlock {
+            block_type: "divider".to_string(),
+            text: None,
+            fields: None,
+        },
+    ];
+
+    for recovery in recoveries {
+        blocks.push(SlackBlock {
+            block_type: "section".to_string(),
+            text: None,
+            fields: Some(vec![
+                SlackText {
+                    text_type: "mrkdwn".to_string(),
+                    text: format!("*Domain:*\n{}", recovery.name),
+                },
+                SlackText {
+                    text_type: "mrkdwn".to_string(),
+                    text: format!("*URL:*\n<{}|{}>", recovery.url, recovery.url),
+                },
+                SlackText {
+                    text_type: "mrkdwn".to_string(),
+                    text: format!(
+                        "*Response Time:*\n{}ms",
+                        recovery.response_time_ms.unwrap_or(0)
+                    ),
+                },
+            ]),
+        });
+    }
+
+    let message = SlackMessage {
+        text: header_text,
+        blocks,
+    };
+
+    let client = Client::new();
+    client
+        .post(webhook_url)
+        .json(&message)
+        .send()
+        .await
+        .map_err(|e| Error::from(e.to_string()))?;
+
+    Ok(())
+}
+
+/// Per-domain view of current state plus recent history, as rendered on the
+/// status dashboard.
+#[derive(Debug, Serialize)]
+struct DomainView {
+    name: String,
+    url: String,
+    current_state: DomainState,
+    last_response_time_ms: Option<u64>,
+    uptime_percentage: f64,
+    history: Vec<HistoryEntry>,
+}
+
+impl DomainView {
+    fn from_history(domain: &Domain, history: Vec<HistoryEntry>) -> Self {
+        let total = history.len();
+        let successes = history.iter().filter(|h| h.success).count();
+        let uptime_percentage = if total == 0 {
+            100.0
+        } else {
+            (successes as f64 / total as f64) * 100.0
+        };
+        let last = history.last();
+
+        DomainView {
+            name: domain.name.clone(),
+            url: domain.url.clone(),
+            current_state: match last {
+                Some(entry) if !entry.success => DomainState::Down,
+                _ => DomainState::Up,
+            },
+            last_response_time_ms: last.and_then(|e| e.response_time_ms),
+            uptime_percentage,
+            history,
+        }
+    }
+}
+
+fn html_escape(input: &str) -> String {
+    input
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn render_dashboard_html(
+    domains: &[DomainView],
+    last_update: DateTime<Utc>,
+    refresh_interval: u64,
+) -> String {
+    let mut rows = String::new();
+    for view in domains {
+        let (badge_class, badge_text) = match view.current_state {
+            DomainState::Up => ("status-up", "Up"),
+            DomainState::Down => ("status-down", "Down"),
+        };
+        rows.push_str(&format!(
+            "<tr><td>{name}</td><td><a href=\"{url}\">{url}</a></td><td><span class=\"{badge_class}\">{badge_text}</span></td><td>{response_time}</td><td>{uptime:.2}%</td></tr>\n",
+            name = html_escape(&view.name),
+            url = html_escape(&view.url),
+            badge_class = badge_class,
+            badge_text = badge_text,
+            response_time = view
+                .last_response_time_ms
+                .map(|ms| format!("{}ms", ms))
+                .unwrap_or_else(|| "-".to_string()),
+            uptime = view.uptime_percentage,
+        ));
+    }
+
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta http-equiv="refresh" content="{refresh_interval}">
+<title>Magnet Watchtower Status</title>
+<style>
+body {{ font-family: -apple-system, sans-serif; background: #0b0e14; color: #e6e6e6; margin: 2rem; }}
+table {{ width: 100%; border-collapse: collapse; }}
+th, td {{ text-align: left; padding: 0.5rem 1rem; border-bottom: 1px solid #222; }}
+.status-up {{ color: #57f287; font-weight: 600; }}
+.status-down {{ color: #ed4245; font-weight: 600; }}
+footer {{ margin-top: 1rem; color: #888; font-size: 0.85rem; }}
+</style>
+</head>
+<body>
+<h1>Magnet Watchtower</h1>
+<table>
+<thead><tr><th>Domain</th><th>URL</th><th>State</th><th>Last Response</th><th>Uptime</th></tr></thead>
+<tbody>
+{rows}</tbody>
+</table>
+<footer>Last updated {last_update} &middot; refreshes every {refresh_interval}s</footer>
+</body>
+</html>
+"#,
+        refresh_interval = refresh_interval,
+        rows = rows,
+        last_update = last_update.to_rfc3339(),
+    )
+}
+
+fn query_param(req: &Request, key: &str) -> Option<String> {
+    req.uri().query()?.split('&').find_map(|pair| {
+        let mut parts = pair.splitn(2, '=');
+        let k = parts.next()?;
+        let v = parts.next().unwrap_or("");
+        if k == key {
+            Some(v.to_string())
+        } else {
+            None
+        }
+    })
+}
+
+/// Distinguishes the scheduled check-and-notify invocation from a plain
+/// browser/API GET against the status dashboard.
+/// Fails closed by default: the check-and-notify path (and the real
+/// notifications it can trigger, including PagerDuty pages) only runs when
+/// `CRON_SECRET` is configured and presented as a bearer token. The
+/// `x-vercel-cron` header and `?check=1` are both client-supplied and
+/// forgeable by anyone hitting the public function URL, so neither is
+/// consulted — without `CRON_SECRET` set, this always returns false and
+/// the endpoint only ever serves the read-only dashboard.
+fn is_cron_request(req: &Request) -> bool {
+    let secret = match std::env::var("CRON_SECRET") {
+        Ok(secret) => secret,
+        Err(_) => {
+            eprintln!(
+                "CRON_SECRET is not set; refusing to run check-and-notify from an unauthenticated request"
+            );
+            return false;
+        }
+    };
+
+    let expected = format!("Bearer {}", secret);
+    req.headers()
+        .get("authorization")
+        .and_then(|v| v.to_str().ok())
+        == Some(expected.as_str())
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Error> {
     run(handler).await
 }
 
-pub async fn handler(_req: Request) -> Result<Response<Body>, Error> {
+pub async fn handler(req: Request) -> Result<Response<Body>, Error> {
+    if is_cron_request(&req) {
+        run_checks_and_notify().await
+    } else {
+        render_dashboard(&req).await
+    }
+}
+
+async fn run_checks_and_notify() -> Result<Response<Body>, Error> {
     // Load domains configuration
     let config_content = include_str!("../domains.json");
     let config: DomainsConfig = serde_json::from_str(config_content)
         .map_err(|e| Error::from(format!("Failed to parse domains.json: {}", e)))?;
 
-    // Create HTTP client
-    let client = Client::builder()
-        .user_agent("MagnetWatchtower/1.0")
-        .build()
-        .map_err(|e| Error::from(e.to_string()))?;
+    // Plain client used for KV REST calls, plus an optional custom DNS
+    // resolver shared by every per-domain client below.
+    let custom_resolver = build_custom_dns_resolver();
+    let http_client = build_domain_client(None, &custom_resolver).map_err(Error::from)?;
+
+    let dns_context = Arc::new(DnsContext {
+        resolver: custom_resolver.clone(),
+    });
+
+    // Check all domains concurrently, capped by a semaphore so a large
+    // domains.json can't exhaust sockets/file descriptors or trip rate
+    // limits. Every task is spawned up front; the semaphore (not batching)
+    // decides who actually runs next, so a fast check frees its permit for
+    // whichever task is waiting rather than waiting on a fixed batch.
+    let max_concurrent_checks: usize = std::env::var("MAX_CONCURRENT_CHECKS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .filter(|&n| n > 0)
+        .unwrap_or(20);
+    let check_semaphore = Arc::new(tokio::sync::Semaphore::new(max_concurrent_checks));
 
-    // Check all domains concurrently
     let mut tasks = Vec::new();
     for domain in &config.domains {
-        let client = client.clone();
         let domain = domain.clone();
+        let dns_context = dns_context.clone();
+        let check_semaphore = check_semaphore.clone();
+        let custom_resolver = custom_resolver.clone();
+
         tasks.push(tokio::spawn(async move {
-            check_domain(&client, &domain).await
+            let _permit = check_semaphore
+                .acquire_owned()
+                .await
+                .expect("check semaphore closed");
+
+            let host = extract_host(&domain.url);
+            let (dns_time_ms, dns_result) = match &host {
+                Some(host) => {
+                    let (elapsed, result) = dns_context.resolve_timed(&domain, host).await;
+                    (Some(elapsed), result)
+                }
+                None => (None, Err("Could not parse hostname from URL".to_string())),
+            };
+
+            let resolved_addr = match dns_result {
+                Ok(addr) => addr,
+                Err(error) => {
+                    return CheckResult {
+                        name: domain.name.clone(),
+                        url: domain.url.clone(),
+                        success: false,
+                        error: Some(error),
+                        status_code: None,
+                        response_time_ms: None,
+                        dns_time_ms,
+                        consecutive_failures: None,
+                    };
+                }
+            };
+
+            // Every domain gets its own client here, pinned via `.resolve()`
+            // to the address just resolved above -- this both avoids a
+            // second, independent lookup when the request connects and
+            // keeps two domains that share a hostname (blue/green origin
+            // checks with different override IPs) from clobbering each
+            // other's pin on a shared `Client`.
+            let host = host.expect("dns_result Ok implies host was parsed");
+            let mut overrides = domain.resolve.clone().unwrap_or_default();
+            overrides.insert(host, resolved_addr.ip().to_string());
+
+            match build_domain_client(Some(&overrides), &custom_resolver) {
+                Ok(domain_client) => check_domain(&domain_client, &domain, dns_time_ms).await,
+                Err(e) => CheckResult {
+                    name: domain.name.clone(),
+                    url: domain.url.clone(),
+                    success: false,
+                    error: Some(format!("Failed to apply resolve override: {}", e)),
+                    status_code: None,
+                    response_time_ms: None,
+                    dns_time_ms,
+                    consecutive_failures: None,
+                },
+            }
         }));
     }
 
@@ -220,21 +1371,64 @@ pub async fn handler(_req: Request) -> Result<Response<Body>, Error> {
         }
     }
 
+    let kv = build_kv_client(&http_client);
+
+    // Run each result through its domain's circuit breaker so we only alert
+    // on state transitions (or backed-off repeats) instead of every tick,
+    // and append it to the domain's persisted history for the dashboard.
+    let breakers = Breakers::new(build_breaker_store(&kv));
+    let history_store = build_history_store(&kv);
+    let mut alert_failures = Vec::new();
+    let mut recoveries = Vec::new();
+    for result in &mut results {
+        let evaluation = breakers.evaluate(result).await;
+        result.consecutive_failures = Some(evaluation.consecutive_failures);
+        if evaluation.should_alert_down {
+            alert_failures.push(result.clone());
+        }
+        if evaluation.should_alert_recovered {
+            recoveries.push(result.clone());
+        }
+
+        history_store
+            .append(
+                &result.name,
+                HistoryEntry {
+                    checked_at: Utc::now(),
+                    success: result.success,
+                    status_code: result.status_code,
+                    response_time_ms: result.response_time_ms,
+                    error: result.error.clone(),
+                },
+            )
+            .await;
+    }
+
     // Filter failures
-    let failures: Vec<CheckResult> = results
-        .iter()
-        .filter(|r| !r.success)
-        .cloned()
-        .collect();
-
-    // Send Slack notification if there are failures
-    if !failures.is_empty() {
-        if let Ok(webhook_url) = std::env::var("SLACK_WEBHOOK_URL") {
-            if let Err(e) = send_slack_notification(&webhook_url, &failures).await {
-                eprintln!("Failed to send Slack notification: {}", e);
+    let failures: Vec<CheckResult> = results.iter().filter(|r| !r.success).cloned().collect();
+
+    // Fan out to every configured notifier concurrently, collecting
+    // per-notifier errors rather than aborting on the first failure.
+    if !alert_failures.is_empty() || !recoveries.is_empty() {
+        let notifiers = build_notifiers();
+        let mut notify_tasks = Vec::new();
+        for notifier in notifiers {
+            let alert_failures = alert_failures.clone();
+            let recoveries = recoveries.clone();
+            notify_tasks.push(tokio::spawn(async move {
+                (
+                    notifier.name(),
+                    notifier.notify(&alert_failures, &recoveries).await,
+                )
+            }));
+        }
+
+        for task in notify_tasks {
+            match task.await {
+                Ok((_name, Ok(()))) => {}
+                Ok((name, Err(e))) => eprintln!("Notifier '{}' failed: {}", name, e),
+                Err(e) => eprintln!("Notifier task panicked: {}", e),
             }
-        } else {
-            eprintln!("SLACK_WEBHOOK_URL not set, skipping notification");
         }
     }
 
@@ -244,6 +1438,7 @@ pub async fn handler(_req: Request) -> Result<Response<Body>, Error> {
         "total_checked": results.len(),
         "successful": results.iter().filter(|r| r.success).count(),
         "failed": failures.len(),
+        "max_concurrent_checks": max_concurrent_checks,
         "results": results,
     });
 
@@ -252,3 +1447,97 @@ pub async fn handler(_req: Request) -> Result<Response<Body>, Error> {
         .header("Content-Type", "application/json")
         .body(Body::from(serde_json::to_string_pretty(&summary)?))?)
 }
+
+/// Renders the public status page: an HTML table by default, or the raw
+/// historical JSON when called with `?format=json`.
+async fn render_dashboard(req: &Request) -> Result<Response<Body>, Error> {
+    let config_content = include_str!("../domains.json");
+    let config: DomainsConfig = serde_json::from_str(config_content)
+        .map_err(|e| Error::from(format!("Failed to parse domains.json: {}", e)))?;
+
+    let client = Client::builder()
+        .user_agent("MagnetWatchtower/1.0")
+        .build()
+        .map_err(|e| Error::from(e.to_string()))?;
+
+    let history_store = build_history_store(&build_kv_client(&client));
+    let refresh_interval: u64 = std::env::var("DASHBOARD_REFRESH_SECONDS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(60);
+
+    let mut domain_views = Vec::new();
+    for domain in &config.domains {
+        let history = history_store.get(&domain.name).await;
+        domain_views.push(DomainView::from_history(domain, history));
+    }
+
+    let last_update = Utc::now();
+
+    if query_param(req, "format").as_deref() == Some("json") {
+        let payload = serde_json::json!({
+            "last_update": last_update.to_rfc3339(),
+            "refresh_interval": refresh_interval,
+            "domains": domain_views,
+        });
+
+        return Ok(Response::builder()
+            .status(StatusCode::OK)
+            .header("Content-Type", "application/json")
+            .body(Body::from(serde_json::to_string_pretty(&payload)?))?);
+    }
+
+    let html = render_dashboard_html(&domain_views, last_update, refresh_interval);
+    Ok(Response::builder()
+        .status(StatusCode::OK)
+        .header("Content-Type", "text/html; charset=utf-8")
+        .body(Body::from(html))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_elapsed_is_quiet_at_zero_and_alerts_at_powers_of_two_below_the_cap() {
+        assert!(!backoff_elapsed(0));
+        assert!(backoff_elapsed(1));
+        assert!(backoff_elapsed(2));
+        assert!(!backoff_elapsed(3));
+        assert!(backoff_elapsed(4));
+        assert!(!backoff_elapsed(5));
+        assert!(backoff_elapsed(32));
+    }
+
+    #[test]
+    fn backoff_elapsed_falls_back_to_the_cap_once_reached() {
+        // 63 is below BREAKER_BACKOFF_CAP and not a power of two: quiet.
+        assert!(!backoff_elapsed(63));
+        // 64 is both the cap and a power of two: alerts either way.
+        assert!(backoff_elapsed(64));
+        // 65 is past the cap and no longer power-of-two-checked, so it only
+        // alerts on multiples of the cap.
+        assert!(!backoff_elapsed(65));
+        // 128 is the next multiple of the cap.
+        assert!(backoff_elapsed(128));
+    }
+
+    #[test]
+    fn parse_resolve_override_accepts_ipv4() {
+        let addr = parse_resolve_override("203.0.113.10").expect("valid IPv4");
+        assert_eq!(addr.ip().to_string(), "203.0.113.10");
+        assert_eq!(addr.port(), 0);
+    }
+
+    #[test]
+    fn parse_resolve_override_accepts_ipv6() {
+        let addr = parse_resolve_override("2001:db8::1").expect("valid IPv6");
+        assert_eq!(addr.ip().to_string(), "2001:db8::1");
+        assert_eq!(addr.port(), 0);
+    }
+
+    #[test]
+    fn parse_resolve_override_rejects_garbage() {
+        assert!(parse_resolve_override("not-an-ip").is_err());
+    }
+}